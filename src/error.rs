@@ -0,0 +1,56 @@
+//! Error taxonomy for the core message loop.
+//!
+//! The executor talks to core over a single long-lived connection, and a malformed frame used to
+//! `panic!` the whole process. [`ExecutorError`] instead turns failures into values the loop acts
+//! on. An unexpected-but-known message is logged and the loop keeps serving, and a read timeout
+//! while draining just polls again. Every other error ends the session and hands control back to
+//! the reconnect loop; fatal ones ([`ExecutorError::is_fatal`]) mean the connection is no longer
+//! trustworthy, and a disconnect or IO failure ends the session without being fatal.
+
+use std::fmt;
+
+/// A failure encountered while serving core's message loop.
+#[derive(Debug)]
+pub enum ExecutorError {
+    /// A frame that could not be parsed — the stream framing is out of sync and unusable.
+    UnparseableProtocol(String),
+    /// The core rejected our identity (e.g. an unexpected register response).
+    AuthFailure(String),
+    /// A well-formed but unexpected message; the connection itself is still fine.
+    UnexpectedMessage(String),
+    /// The peer went away. Not fatal to the executor — the reconnect loop will retry.
+    Disconnected,
+    /// A read timed out with no message available. Used while draining to poll for idleness.
+    ReadTimeout,
+    /// An IO failure writing to, or reading from, the connection.
+    Io(String),
+}
+
+impl ExecutorError {
+    /// Whether this error means the current connection is no longer trustworthy.
+    ///
+    /// Only unparseable protocol and auth failures are fatal. A disconnect or IO failure also ends
+    /// the session but is not fatal; an unexpected-but-known message is recoverable and the loop
+    /// keeps serving.
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            ExecutorError::UnparseableProtocol(_) | ExecutorError::AuthFailure(_)
+        )
+    }
+}
+
+impl fmt::Display for ExecutorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutorError::UnparseableProtocol(d) => write!(f, "unparseable protocol frame: {d}"),
+            ExecutorError::AuthFailure(d) => write!(f, "core rejected registration: {d}"),
+            ExecutorError::UnexpectedMessage(d) => write!(f, "unexpected message from core: {d}"),
+            ExecutorError::Disconnected => write!(f, "core disconnected"),
+            ExecutorError::ReadTimeout => write!(f, "read timed out"),
+            ExecutorError::Io(d) => write!(f, "io error talking to core: {d}"),
+        }
+    }
+}
+
+impl std::error::Error for ExecutorError {}
@@ -0,0 +1,259 @@
+//! An [`Executor`] that drives a build from a Lua `goodfile` instead of the static job list.
+//!
+//! When a build request names a `goodfile`, it is evaluated in an embedded Lua VM that
+//! exposes a small build API:
+//!
+//! * `cmd(...)` — run a shell/Docker step, returning a `CommandOutput { exit_status, stdout,
+//!   stderr }` table the script can branch on;
+//! * `artifact(path, name, desc)` — mark a path as a build output;
+//! * `metric(name, value)` — record a named numeric metric.
+//!
+//! Each `cmd` invocation is mapped onto the existing Docker execution path via an inner
+//! [`SimpleDockerExecutor`], so conditional/multi-step builds reuse the same container plumbing as
+//! the flat job array. When the named `goodfile` cannot be read, [`DEFAULT_GOODFILE`] is used
+//! instead.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use anyhow::Result;
+use rlua::Lua;
+use tracing::warn;
+use waterlib::executors::simple_docker::{SimpleDockerExecutor, SimpleDockerExecutorConfig};
+use waterlib::executors::{CommandOutput, Executor, ExecutorEvent};
+use waterlib::net::{Job, JobBuildRequestMessage, JobResult};
+
+/// Build script used when no `goodfile` can be read for the build request: a single step that runs
+/// the job's configured command, matching the behaviour of the static job list.
+pub const DEFAULT_GOODFILE: &str = r#"
+-- Default goodfile: run the job's command as a single step. A non-zero exit is reported as a
+-- failed build through the job result, exactly like the Docker path — it is not raised as an error.
+cmd(job.command)
+"#;
+
+/// Rewrite an inner step event so its `job_id` is the parent goodfile job's rather than the
+/// throwaway id of the per-`cmd` step that emitted it. Every `cmd` runs its own
+/// [`SimpleDockerExecutor`] with a fresh step job id; relabelling lets core reassemble a build's
+/// live output (`JobStarted`/`JobOutputChunk`/`JobStepChanged`) against the single `JobFinished`
+/// that carries the parent job id.
+fn relabel(event: ExecutorEvent, job_id: &str) -> ExecutorEvent {
+    match event {
+        ExecutorEvent::Started { .. } => ExecutorEvent::Started {
+            job_id: job_id.to_string(),
+        },
+        ExecutorEvent::Output { stream, bytes, .. } => ExecutorEvent::Output {
+            job_id: job_id.to_string(),
+            stream,
+            bytes,
+        },
+        ExecutorEvent::StepChanged { step, .. } => ExecutorEvent::StepChanged {
+            job_id: job_id.to_string(),
+            step,
+        },
+        ExecutorEvent::Artifact {
+            name,
+            desc,
+            seq,
+            bytes,
+            ..
+        } => ExecutorEvent::Artifact {
+            job_id: job_id.to_string(),
+            name,
+            desc,
+            seq,
+            bytes,
+        },
+        ExecutorEvent::ArtifactComplete { name, sha256, .. } => ExecutorEvent::ArtifactComplete {
+            job_id: job_id.to_string(),
+            name,
+            sha256,
+        },
+    }
+}
+
+/// A declared build output, collected from `artifact(...)` calls during evaluation.
+#[derive(Debug, Clone)]
+pub struct DeclaredArtifact {
+    pub path: String,
+    pub name: String,
+    pub desc: String,
+}
+
+/// Accumulated state threaded through the Lua build API for a single evaluation.
+#[derive(Default)]
+struct BuildState {
+    artifacts: Vec<DeclaredArtifact>,
+    metrics: Vec<(String, f64)>,
+    /// Output of each `cmd(...)` step, in execution order, used to derive the terminal result.
+    steps: Vec<CommandOutput>,
+}
+
+/// Executes a repository's build by evaluating its `goodfile`.
+pub struct LuaExecutor {
+    inner: SimpleDockerExecutor,
+}
+
+impl LuaExecutor {
+    pub fn new(artifact_chunk_size: usize) -> Self {
+        Self {
+            inner: SimpleDockerExecutor::new(SimpleDockerExecutorConfig { artifact_chunk_size }),
+        }
+    }
+
+    /// Pick the script to evaluate: the `goodfile` at the path the build request names, if it
+    /// names one and the file can be read, otherwise the built-in default.
+    ///
+    /// `job.goodfile` carries the repo config's goodfile name. The path is read as given, so it
+    /// resolves against the executor process's working directory; this executor makes no checkout
+    /// of the repository to read it from. A request that names no goodfile, or one whose file
+    /// cannot be read, falls back to [`DEFAULT_GOODFILE`].
+    fn script_for(job: &JobBuildRequestMessage) -> String {
+        let Some(path) = job.goodfile.as_deref() else {
+            return DEFAULT_GOODFILE.to_string();
+        };
+        match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                warn!("Could not read goodfile {path}, falling back to default: {e}");
+                DEFAULT_GOODFILE.to_string()
+            }
+        }
+    }
+}
+
+impl Executor for LuaExecutor {
+    fn execute(
+        &mut self,
+        job: JobBuildRequestMessage,
+        sink: &mut dyn FnMut(ExecutorEvent) -> Result<()>,
+    ) -> Result<JobResult> {
+        let script = Self::script_for(&job);
+        // The id core knows this build by: every streamed event is relabelled to it so live output
+        // reassembles against the terminal result, which carries the same id.
+        let parent_id = JobResult::from_job(&job).job_id;
+        let state = Rc::new(RefCell::new(BuildState::default()));
+
+        let lua = Lua::new();
+        // The `cmd` callback borrows `&mut self.inner` and the `sink` parameter, neither of which
+        // is `'static`, so the build API is registered inside a `scope` — `create_function_mut`
+        // on the context alone requires `'static` closures and would not compile.
+        let eval = lua.context(|ctx| -> Result<()> {
+            ctx.scope(|scope| -> Result<()> {
+                let globals = ctx.globals();
+
+                // `cmd(command)` runs a Docker step through the inner executor, records its output
+                // for the terminal result, and returns the CommandOutput as a Lua table so scripts
+                // can branch on it.
+                let inner = &mut self.inner;
+                let repo_url = job.repo_url.clone();
+                let reference = job.reference.clone();
+                let state_steps = Rc::clone(&state);
+                let parent_id = parent_id.clone();
+                let cmd = scope.create_function_mut(move |ctx, command: String| {
+                    let step = JobBuildRequestMessage {
+                        repo_url: repo_url.clone(),
+                        reference: reference.clone(),
+                        job: Job::from_command(command),
+                        goodfile: None,
+                        artifacts: Vec::new(),
+                    };
+                    let res = inner
+                        .execute(step, &mut |event| sink(relabel(event, &parent_id)))
+                        .map_err(rlua::Error::external)?;
+                    let output = res.output.clone();
+                    state_steps.borrow_mut().steps.push(output.clone());
+                    let CommandOutput {
+                        exit_status,
+                        stdout,
+                        stderr,
+                    } = output;
+                    let table = ctx.create_table()?;
+                    table.set("exit_status", exit_status)?;
+                    table.set("stdout", stdout)?;
+                    table.set("stderr", stderr)?;
+                    Ok(table)
+                })?;
+                globals.set("cmd", cmd)?;
+
+                // `artifact(path, name, desc)` records an output to copy out of the build once it
+                // finishes; `desc` rides the wire alongside the bytes so core can label downloads.
+                let state_artifacts = Rc::clone(&state);
+                let artifact = scope.create_function_mut(
+                    move |_, (path, name, desc): (String, String, String)| {
+                        state_artifacts
+                            .borrow_mut()
+                            .artifacts
+                            .push(DeclaredArtifact { path, name, desc });
+                        Ok(())
+                    },
+                )?;
+                globals.set("artifact", artifact)?;
+
+                let state_metrics = Rc::clone(&state);
+                let metric = scope.create_function_mut(move |_, (name, value): (String, f64)| {
+                    state_metrics.borrow_mut().metrics.push((name, value));
+                    Ok(())
+                })?;
+                globals.set("metric", metric)?;
+
+                // Expose the job being built as a read-only `job` table so scripts — including the
+                // default one — can reach its command and name without a separate global per field.
+                let job_table = ctx.create_table()?;
+                job_table.set("name", job.job.name.clone())?;
+                job_table.set("command", job.job.command.clone())?;
+                globals.set("job", job_table)?;
+
+                ctx.load(&script).exec()?;
+                Ok(())
+            })
+        });
+
+        // Surface the collected metrics/artifacts through the job result the same way the Docker
+        // executor does. A non-zero step exit is a *failed build*, reported as a normal JobResult
+        // with a failing `exit_status`, not a Rust `Err` — that keeps the result contract and the
+        // notifier's pass/fail decision intact.
+        let mut job_result = JobResult::from_job(&job);
+        let state = state.borrow();
+        for (name, value) in &state.metrics {
+            job_result.metrics.push((name.clone(), *value));
+        }
+        for artifact in &state.artifacts {
+            job_result
+                .artifacts
+                .push((artifact.name.clone(), artifact.path.clone()));
+        }
+        // Copy the declared outputs out of the build workspace before the inner executor tears it
+        // down, streaming each (with its `desc`) to core through the same sink as live output and
+        // tagged with this build's job id. A failed copy is logged, not fatal — a missing output
+        // must not fail an otherwise-green build.
+        if !state.artifacts.is_empty() {
+            let specs: Vec<(String, String, String)> = state
+                .artifacts
+                .iter()
+                .map(|a| (a.name.clone(), a.path.clone(), a.desc.clone()))
+                .collect();
+            if let Err(e) = self.inner.collect_artifacts(&job, &specs, sink) {
+                warn!("artifact collection failed: {e}");
+            }
+        }
+        // Terminal status: the first failing step wins, otherwise the last step's output.
+        if let Some(output) = state
+            .steps
+            .iter()
+            .find(|o| o.exit_status != 0)
+            .or_else(|| state.steps.last())
+        {
+            job_result.output = output.clone();
+        }
+        // A goodfile that raises a Lua error (bad script, explicit `error(...)`) still yields a
+        // JobResult: mark it failed so core and the notifiers learn the build ran and failed.
+        if let Err(e) = eval {
+            warn!("goodfile evaluation failed: {e}");
+            if job_result.output.exit_status == 0 {
+                job_result.output.exit_status = 1;
+                job_result.output.stderr = format!("goodfile evaluation failed: {e}");
+            }
+        }
+        Ok(job_result)
+    }
+}
@@ -10,61 +10,163 @@ use std::io::{ErrorKind, Read};
 use std::net::TcpStream;
 use std::path::Path;
 use std::process::exit;
-use tracing::{debug, error, info, instrument, span, Level};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::sleep;
+use std::time::Duration;
+use tracing::{debug, error, info, instrument, span, warn, Level};
 use tracing_subscriber;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{fmt, EnvFilter};
 use waterlib::executors::simple_docker::{SimpleDockerExecutor, SimpleDockerExecutorConfig};
-use waterlib::executors::Executor;
+use waterlib::executors::{Executor, ExecutorEvent};
 use waterlib::net::ExecutorMessage::ExecutorRegister;
-use waterlib::net::{ExecutorMessage, ExecutorStatus, JobBuildRequestMessage};
+use waterlib::net::{ExecutorMessage, ExecutorStatus, Job, JobBuildRequestMessage};
 
-#[derive(Deserialize, Debug)]
+mod error;
+mod lua_executor;
+mod notifier;
+use error::ExecutorError;
+use lua_executor::LuaExecutor;
+use notifier::{BuildState, Notification, Notifier, NotifierConfig};
+
+/// Fully-resolved executor configuration. Built once at startup by layering the config sources
+/// (see [`get_config`]); no field reaches back into the environment after this point.
+#[derive(Debug)]
 struct Config {
-    #[serde(default = "Config::default_core_host")]
     core_host: String,
-    #[serde(default = "Config::default_core_port")]
     core_port: u32,
+    /// Upper bound, in seconds, for the exponential reconnect backoff.
+    max_backoff_secs: u64,
+    /// Number of reconnect attempts before giving up; 0 means retry forever.
+    max_retries: u32,
+    /// Maximum number of jobs this node will run at once.
+    max_concurrency: usize,
+    /// External targets notified of build status as jobs start and finish.
+    notifiers: Vec<NotifierConfig>,
+    /// Size, in bytes, of each artifact chunk streamed back to core.
+    artifact_chunk_size: usize,
 }
-impl Config {
-    fn default_core_host() -> String {
-        if let Ok(host) = env::var("WATERCI_CORE_HOST") {
-            return host;
-        }
-        "127.0.0.1".to_string()
-    }
-    fn default_core_port() -> u32 {
-        if let Ok(port) = env::var("WATERCI_CORE_PORT") {
-            match port.parse::<u32>() {
-                Ok(i) => {
-                    return i;
-                }
-                Err(_) => {}
-            }
+
+const DEFAULT_CORE_HOST: &str = "127.0.0.1";
+const DEFAULT_CORE_PORT: u32 = 5633;
+const DEFAULT_MAX_BACKOFF_SECS: u64 = 60;
+const DEFAULT_MAX_RETRIES: u32 = 0;
+const DEFAULT_MAX_CONCURRENCY: usize = 1;
+/// Default artifact chunk size, in bytes, when the operator does not override it.
+const DEFAULT_ARTIFACT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// One config layer: every field optional so an absent value falls through to the next layer.
+/// The CLI-selected YAML file deserializes into this shape; [`RawConfig::from_env`] fills it from
+/// the `WATERCI_*` environment.
+#[derive(Deserialize, Debug, Default)]
+struct RawConfig {
+    core_host: Option<String>,
+    core_port: Option<u32>,
+    max_backoff_secs: Option<u64>,
+    max_retries: Option<u32>,
+    max_concurrency: Option<usize>,
+    notifiers: Option<Vec<NotifierConfig>>,
+    artifact_chunk_size: Option<usize>,
+}
+
+impl RawConfig {
+    /// Collect the `WATERCI_*` overrides into a layer. A variable that is unset or fails to parse
+    /// contributes nothing, leaving the field to the layers below.
+    fn from_env() -> Self {
+        Self {
+            core_host: env::var("WATERCI_CORE_HOST").ok(),
+            core_port: parse_env("WATERCI_CORE_PORT"),
+            max_backoff_secs: parse_env("WATERCI_MAX_BACKOFF_SECS"),
+            max_retries: parse_env("WATERCI_MAX_RETRIES"),
+            max_concurrency: parse_env::<usize>("WATERCI_MAX_CONCURRENCY").filter(|&n| n > 0),
+            notifiers: None,
+            artifact_chunk_size: parse_env::<usize>("WATERCI_ARTIFACT_CHUNK_SIZE")
+                .filter(|&n| n > 0),
         }
-        5633
     }
 }
-impl Default for Config {
-    fn default() -> Self {
+
+/// Parse a single `WATERCI_*` variable, yielding `None` when unset or malformed.
+fn parse_env<T: std::str::FromStr>(key: &str) -> Option<T> {
+    env::var(key).ok().and_then(|v| v.parse().ok())
+}
+
+impl Config {
+    /// Resolve the final config by layering `yaml` over `env` over the built-in defaults, so the
+    /// precedence is `CLI --config-file YAML > WATERCI_* env vars > built-in defaults`. Dotenv
+    /// values fold into the `env` layer because [`merge_dotenv`] loads them into the process
+    /// environment before this runs (see its precedence note).
+    fn resolve(yaml: RawConfig, env: RawConfig) -> Self {
         Self {
-            core_host: Config::default_core_host(),
-            core_port: Config::default_core_port(),
+            core_host: yaml
+                .core_host
+                .or(env.core_host)
+                .unwrap_or_else(|| DEFAULT_CORE_HOST.to_string()),
+            core_port: yaml.core_port.or(env.core_port).unwrap_or(DEFAULT_CORE_PORT),
+            max_backoff_secs: yaml
+                .max_backoff_secs
+                .or(env.max_backoff_secs)
+                .unwrap_or(DEFAULT_MAX_BACKOFF_SECS),
+            max_retries: yaml
+                .max_retries
+                .or(env.max_retries)
+                .unwrap_or(DEFAULT_MAX_RETRIES),
+            // Clamp to at least one worker: a `max_concurrency: 0` from YAML (the env layer already
+            // filters it) would spawn zero workers, so jobs would enqueue, `inflight` would never
+            // decrement, and the node would wedge as permanently `Busy`.
+            max_concurrency: yaml
+                .max_concurrency
+                .or(env.max_concurrency)
+                .unwrap_or(DEFAULT_MAX_CONCURRENCY)
+                .max(1),
+            notifiers: yaml.notifiers.or(env.notifiers).unwrap_or_default(),
+            artifact_chunk_size: yaml
+                .artifact_chunk_size
+                .or(env.artifact_chunk_size)
+                .unwrap_or(DEFAULT_ARTIFACT_CHUNK_SIZE),
         }
     }
 }
 
+/// Pick and load the dotenv file for the active environment before any config is read.
+///
+/// The file is chosen from the `ENV` variable — `production` → `.env.production`,
+/// `development` → `.env.development`, anything else (including unset) → plain `.env` — and loaded
+/// without clobbering variables already present in the real environment. This establishes the
+/// `WATERCI_* env vars > dotenv file` half of the precedence chain: dotenv only fills variables the
+/// real environment has not already set, and both feed the single `WATERCI_*` layer that
+/// [`Config::resolve`] lays under the CLI-selected YAML and over the built-in defaults.
+fn merge_dotenv() {
+    let file = match env::var("ENV").as_deref() {
+        Ok("production") => ".env.production",
+        Ok("development") => ".env.development",
+        _ => ".env",
+    };
+    match dotenvy::from_filename(file) {
+        Ok(_) => debug!("Loaded environment from {file}"),
+        Err(e) if e.not_found() => debug!("No {file} to load, using process environment only"),
+        Err(e) => error!("Failed to load {file}: {e}"),
+    }
+}
+
+/// Build the merged [`Config`], layering the optional CLI-selected YAML file on top of the
+/// `WATERCI_*` environment (see [`merge_dotenv`]) and the built-in defaults. A YAML file only needs
+/// to set the fields it wants to override; everything absent falls back through the env/default
+/// chain in [`Config::resolve`].
 fn get_config(path: &str) -> anyhow::Result<Config> {
     let p = Path::new(path);
-    if p.exists() {
+    let yaml = if p.exists() {
         let mut f = File::open(p)?;
         let mut s = String::new();
         f.read_to_string(&mut s)?;
-        let c = serde_yaml::from_str(&s)?;
-        return Ok(c);
-    }
-    Ok(Config::default())
+        serde_yaml::from_str(&s)?
+    } else {
+        RawConfig::default()
+    };
+    Ok(Config::resolve(yaml, RawConfig::from_env()))
 }
 
 #[derive(Parser, Debug)]
@@ -97,6 +199,7 @@ fn try_init_sentry() -> Option<sentry::ClientInitGuard> {
 #[instrument]
 fn main() {
     let _guard = try_init_sentry();
+    merge_dotenv();
     let args = Args::parse();
     let conf = get_config(&args.config_file).expect("Could not read config file");
     match run(conf) {
@@ -108,71 +211,419 @@ fn main() {
     };
 }
 
-#[instrument]
-fn run(config: Config) -> Result<()> {
+/// Establish a TCP connection to core and complete the `ExecutorRegister` handshake,
+/// returning the live stream and the executor id core assigned us.
+fn connect_and_register(config: &Config) -> Result<(TcpStream, String)> {
     let mut stream = TcpStream::connect(format!("{}:{}", &config.core_host, config.core_port))?;
     debug!("Connected to server, trying to register");
     ExecutorRegister.serialize(&mut Serializer::new(&mut stream))?;
     debug!("Sent register request, waiting on response");
-    let resp = ExecutorMessage::deserialize(&mut Deserializer::new(&mut stream))
-        .expect("could not read register response");
+    let resp = ExecutorMessage::deserialize(&mut Deserializer::new(&mut stream))?;
     let uid = if let ExecutorMessage::ExecutorRegisterResponse { id } = resp {
         id
     } else {
-        panic!("Invalid response from server: {resp:?}");
+        return Err(ExecutorError::AuthFailure(format!("{resp:?}")).into());
     };
     info!("Successfully connected and logged in the core server as executor {uid}");
+    Ok((stream, uid))
+}
+
+/// Reconnect loop: keep (re-)establishing a connection to core, draining its message
+/// loop, and — on a clean disconnect — sleeping with jittered exponential backoff before
+/// trying again. Honours `max_retries` (0 = infinite) and caps the delay at `max_backoff_secs`.
+#[instrument]
+fn run(config: Config) -> Result<()> {
+    // One notifier, shared across reconnects, fans build status out to the configured targets.
+    let notifier = Notifier::new(config.notifiers.clone());
+    let mut attempt: u32 = 0;
+    loop {
+        match connect_and_register(&config) {
+            Ok((stream, uid)) => {
+                // A successful session resets the backoff schedule.
+                attempt = 0;
+                // A session that ends in error (e.g. a failed socket clone) must not escape `run`
+                // and kill the process — it is just another disconnect, routed through the same
+                // backoff-and-retry path as a clean one.
+                match serve(&config, stream, uid, &notifier) {
+                    Ok(()) => info!("Disconnected from core, will attempt to reconnect"),
+                    Err(e) => error!("Core session ended with error: {e}, will reconnect"),
+                }
+            }
+            Err(e) => {
+                error!("Failed to connect to core: {e}");
+            }
+        }
+
+        attempt += 1;
+        if config.max_retries != 0 && attempt > config.max_retries {
+            error!("Giving up after {} reconnect attempts", config.max_retries);
+            break;
+        }
+
+        let delay = reconnect_delay(attempt, config.max_backoff_secs);
+        info!("Reconnecting in {delay:?} (attempt {attempt})");
+        sleep(delay);
+    }
+    Ok(())
+}
+
+/// Compute the backoff delay for the given 1-based attempt: `2^(attempt-1)` seconds capped at
+/// `max_backoff_secs`, plus up to one second of random jitter to avoid a thundering herd of
+/// executors all reconnecting in lockstep after a core restart.
+fn reconnect_delay(attempt: u32, max_backoff_secs: u64) -> Duration {
+    let base = 1u64.checked_shl(attempt.saturating_sub(1)).unwrap_or(u64::MAX);
+    let capped = base.min(max_backoff_secs.max(1));
+    let jitter = Duration::from_millis((rand::random::<f64>() * 1000.0) as u64);
+    Duration::from_secs(capped) + jitter
+}
+
+/// Run a single job to completion, forwarding its live output and terminal result down `out`.
+///
+/// Output chunks carry a per-job monotonically increasing `seq` so core can reassemble them in
+/// order, and a single final `JobFinished` carrying the job result preserves the pre-streaming
+/// result contract.
+fn execute_job(
+    job: JobBuildRequestMessage,
+    out: &Sender<ExecutorMessage>,
+    notifier: &Notifier,
+    artifact_chunk_size: usize,
+) -> Result<()> {
+    // Capture the identifying fields up front: `job` is moved into the executor below, but both the
+    // opening and closing notifications describe the same repo/ref/job.
+    let repo_url = job.repo_url.clone();
+    let reference = job.reference.clone();
+    let job_name = job.job.name.clone();
+
+    // Announce the job is underway before we touch the network or Docker.
+    notifier.notify(Notification {
+        repo_url: repo_url.clone(),
+        reference: reference.clone(),
+        job_name: job_name.clone(),
+        state: BuildState::Pending,
+    });
+
+    // A repo that ships a `goodfile` drives its build imperatively through the Lua executor;
+    // everything else takes the plain Docker path over the job's command.
+    let mut executor: Box<dyn Executor> = if job.goodfile.is_some() {
+        Box::new(LuaExecutor::new(artifact_chunk_size))
+    } else {
+        Box::new(SimpleDockerExecutor::new(SimpleDockerExecutorConfig {
+            artifact_chunk_size,
+        }))
+    };
+    let mut seq: u64 = 0;
+    // The executor copies each declared artifact out of the container *before* it tears it down
+    // and streams the bytes through this same sink as `Artifact`/`ArtifactComplete` events, which
+    // we forward verbatim — so artifacts ride the live stream rather than a post-hoc pass that
+    // would race container removal.
+    let res = executor.execute(job, &mut |event| {
+        let msg = match event {
+            ExecutorEvent::Started { job_id } => ExecutorMessage::JobStarted { job_id },
+            ExecutorEvent::Output {
+                job_id,
+                stream,
+                bytes,
+            } => {
+                let n = seq;
+                seq += 1;
+                ExecutorMessage::JobOutputChunk {
+                    job_id,
+                    stream,
+                    seq: n,
+                    bytes,
+                }
+            }
+            ExecutorEvent::StepChanged { job_id, step } => {
+                ExecutorMessage::JobStepChanged { job_id, step }
+            }
+            ExecutorEvent::Artifact {
+                job_id,
+                name,
+                desc,
+                seq,
+                bytes,
+            } => ExecutorMessage::Artifact {
+                job_id,
+                name,
+                desc,
+                seq,
+                chunk: bytes,
+            },
+            ExecutorEvent::ArtifactComplete {
+                job_id,
+                name,
+                sha256,
+            } => ExecutorMessage::ArtifactComplete {
+                job_id,
+                name,
+                sha256,
+            },
+        };
+        out.send(msg).map_err(anyhow::Error::from)
+    })?;
+    // Report the terminal pass/fail state outward before handing the result back to core.
+    notifier.notify(Notification {
+        repo_url,
+        reference,
+        job_name,
+        state: if res.output.exit_status == 0 {
+            BuildState::Success
+        } else {
+            BuildState::Failure
+        },
+    });
+    // A single terminal frame carries the result: `JobFinished` is the streaming-protocol
+    // result-carrier, so we no longer also send a `JobResult` — emitting both made a
+    // streaming-aware core record the same terminal result twice.
+    out.send(ExecutorMessage::JobFinished {
+        job_id: res.job_id.clone(),
+        result: res,
+    })?;
+    Ok(())
+}
+
+/// Drain core's message loop over an established connection until a clean disconnect.
+///
+/// Jobs are dispatched onto a fixed pool of `max_concurrency` worker threads, and every outbound
+/// frame — from workers and from the read loop alike — is funnelled through a single mpsc channel
+/// drained by a dedicated writer thread, so concurrent jobs never interleave bytes on the wire.
+fn serve(config: &Config, mut stream: TcpStream, uid: String, notifier: &Notifier) -> Result<()> {
+    let (out_tx, out_rx) = mpsc::channel::<ExecutorMessage>();
+    let mut write_stream = stream.try_clone()?;
+    let writer = std::thread::spawn(move || {
+        while let Ok(msg) = out_rx.recv() {
+            if let Err(e) = msg.write(&mut write_stream) {
+                error!("Failed to write message to core: {e}");
+                break;
+            }
+        }
+    });
+
+    // Job queue feeding the worker pool. It is deliberately *unbounded*: the read loop enqueues
+    // inline, so a bounded channel would block the loop in `send` once full — and a blocked loop
+    // can no longer read or answer `ExecutorStatusQuery`, making a saturated node go silent. Flow
+    // control instead comes from `inflight`, which counts accepted-but-unfinished jobs so status
+    // queries report `Busy`/`free_slots` accurately and a well-behaved core stops feeding us.
+    let (job_tx, job_rx) = mpsc::channel::<JobBuildRequestMessage>();
+    let job_rx = Arc::new(Mutex::new(job_rx));
+    let inflight = Arc::new(AtomicUsize::new(0));
+    let mut workers = Vec::with_capacity(config.max_concurrency);
+    for _ in 0..config.max_concurrency {
+        let job_rx = Arc::clone(&job_rx);
+        let out_tx = out_tx.clone();
+        let inflight = Arc::clone(&inflight);
+        let notifier = notifier.clone();
+        let artifact_chunk_size = config.artifact_chunk_size;
+        workers.push(std::thread::spawn(move || loop {
+            let job = {
+                let rx = job_rx.lock().expect("job queue poisoned");
+                rx.recv()
+            };
+            let Ok(job) = job else { break };
+            if let Err(e) = execute_job(job, &out_tx, &notifier, artifact_chunk_size) {
+                error!("Job execution failed: {e}");
+            }
+            inflight.fetch_sub(1, Ordering::SeqCst);
+        }));
+    }
+
+    // `draining` is flipped on once core asks us to close: we stop accepting new work but keep
+    // serving the loop — answering status queries with `Draining` — until the in-flight jobs
+    // finish, then close cleanly. While draining we give the socket a short read timeout so the
+    // loop wakes to re-check `inflight` even when core sends nothing further.
+    let mut draining = false;
     loop {
         let span = span!(Level::TRACE, "executor_mainloop", uid = uid.as_str());
         let _enter = span.enter();
         debug!("Waiting on message from core…");
-        match ExecutorMessage::deserialize(&mut Deserializer::new(&mut stream)) {
-            Ok(msg) => {
-                debug!("Got message from core: {msg:?}");
-                match msg {
-                    ExecutorMessage::BuildRequest(req) => {
-                        info!("Got build request from core: {req:?}");
-                        let mut executor = SimpleDockerExecutor::new(SimpleDockerExecutorConfig {});
-                        for job in req.repo_config.jobs {
-                            let job_build_request_message = JobBuildRequestMessage {
+        match handle_core_message(&mut stream, config, &job_tx, &out_tx, &inflight, draining) {
+            Ok(Disposition::Continue) => {}
+            Ok(Disposition::Drain) => {
+                let remaining = inflight.load(Ordering::SeqCst);
+                info!("Core asked us to close; draining {remaining} in-flight job(s)");
+                draining = true;
+                // Wake periodically so we notice the workers finishing.
+                let _ = stream.set_read_timeout(Some(Duration::from_millis(250)));
+            }
+            // While draining, a read timeout just means "no new message" — fall through to the
+            // idle check below rather than tearing the connection down.
+            Err(ExecutorError::ReadTimeout) => {}
+            Err(e) => {
+                if matches!(e, ExecutorError::UnexpectedMessage(_)) {
+                    // Recoverable: a well-formed message we don't handle. Log and keep serving.
+                    warn!("Ignoring unexpected message from core: {e}");
+                    continue;
+                }
+                // Everything else ends the session; the reconnect loop in `run` takes over.
+                if e.is_fatal() {
+                    // Best-effort: let core record why this executor dropped before we close.
+                    let _ = out_tx.send(ExecutorMessage::Error {
+                        detail: e.to_string(),
+                    });
+                }
+                error!("Ending core session: {e}");
+                break;
+            }
+        }
+
+        if draining && inflight.load(Ordering::SeqCst) == 0 {
+            info!("Drain complete, closing connection");
+            break;
+        }
+    }
+
+    // Tear down the pool: dropping the senders lets the workers drain any in-flight jobs and the
+    // writer flush any queued frames before both exit cleanly.
+    drop(job_tx);
+    for worker in workers {
+        let _ = worker.join();
+    }
+    drop(out_tx);
+    let _ = writer.join();
+    Ok(())
+}
+
+/// What the caller should do after handling a single core message.
+enum Disposition {
+    /// Keep serving the loop.
+    Continue,
+    /// Core asked us to close: stop accepting work and drain in-flight jobs before closing.
+    Drain,
+}
+
+/// Read and dispatch one message from core.
+///
+/// Returns a [`Disposition`] on success, or an [`ExecutorError`] the serve loop classifies into a
+/// log-and-continue (recoverable) or tear-down (fatal/disconnect) outcome. Nothing in here
+/// `panic!`s: a malformed frame becomes a structured error rather than crashing the executor.
+fn handle_core_message(
+    stream: &mut TcpStream,
+    config: &Config,
+    job_tx: &Sender<JobBuildRequestMessage>,
+    out_tx: &Sender<ExecutorMessage>,
+    inflight: &AtomicUsize,
+    draining: bool,
+) -> std::result::Result<Disposition, ExecutorError> {
+    let msg = ExecutorMessage::deserialize(&mut Deserializer::new(stream)).map_err(classify_read)?;
+    debug!("Got message from core: {msg:?}");
+    match msg {
+        ExecutorMessage::BuildRequest(req) => {
+            if draining {
+                warn!("Refusing build request while draining: {req:?}");
+                return Ok(Disposition::Continue);
+            }
+            info!("Got build request from core: {req:?}");
+            // The executor is chosen once per repo, not per job entry. When the repo ships a
+            // goodfile it *is* the build: it is evaluated a single time (even if the static job
+            // list is empty, and without re-running it once per listed job). Otherwise every
+            // statically-declared job runs on the plain Docker path.
+            match req.repo_config.goodfile.clone() {
+                Some(goodfile) => {
+                    // The goodfile drives its own steps, so the carrier job only needs an
+                    // identity; reuse the first declared job when present, else a bare one.
+                    let job = req
+                        .repo_config
+                        .jobs
+                        .first()
+                        .cloned()
+                        .unwrap_or_else(|| Job::from_command(String::new()));
+                    inflight.fetch_add(1, Ordering::SeqCst);
+                    job_tx
+                        .send(JobBuildRequestMessage {
+                            repo_url: req.repo_url.clone(),
+                            reference: req.reference.clone(),
+                            job,
+                            goodfile: Some(goodfile),
+                            // Repo-level artifact declarations; the goodfile may add more via its
+                            // `artifact(...)` API.
+                            artifacts: req.repo_config.artifacts.clone(),
+                        })
+                        .map_err(|e| ExecutorError::Io(e.to_string()))?;
+                }
+                None => {
+                    for job in req.repo_config.jobs {
+                        inflight.fetch_add(1, Ordering::SeqCst);
+                        job_tx
+                            .send(JobBuildRequestMessage {
                                 repo_url: req.repo_url.clone(),
                                 reference: req.reference.clone(),
                                 job,
-                            };
-
-                            let res = executor.execute(job_build_request_message)?;
-                            ExecutorMessage::JobResult(res).write(&mut stream)?;
-                        }
-                    }
-                    ExecutorMessage::ExecutorStatusQuery => {
-                        ExecutorMessage::ExecutorStatusResponse(ExecutorStatus::Available)
-                            .write(&mut stream)?;
-                    }
-                    ExecutorMessage::CloseConnection(_uid) => {
-                        break;
-                    }
-                    _ => {
-                        panic!("invalid message from core: {msg:?}");
+                                goodfile: None,
+                                artifacts: req.repo_config.artifacts.clone(),
+                            })
+                            .map_err(|e| ExecutorError::Io(e.to_string()))?;
                     }
                 }
             }
-            Err(e) => match e {
-                Error::InvalidMarkerRead(e) => match e.kind() {
-                    ErrorKind::ConnectionReset
-                    | ErrorKind::ConnectionAborted
-                    | ErrorKind::UnexpectedEof => {
-                        error!("Core has disconnected");
-                        break;
-                    }
-                    kind => {
-                        panic!("Unhandled error while reading core message: {kind:?}");
-                    }
-                },
-                _ => {
-                    panic!("Error communicating with core: {e}");
+            Ok(Disposition::Continue)
+        }
+        ExecutorMessage::ExecutorStatusQuery => {
+            let running = inflight.load(Ordering::SeqCst);
+            let status = if draining {
+                ExecutorStatus::Draining
+            } else if running >= config.max_concurrency {
+                ExecutorStatus::Busy
+            } else {
+                ExecutorStatus::Available {
+                    free_slots: config.max_concurrency - running,
                 }
-            },
+            };
+            out_tx
+                .send(ExecutorMessage::ExecutorStatusResponse(status))
+                .map_err(|e| ExecutorError::Io(e.to_string()))?;
+            Ok(Disposition::Continue)
         }
+        ExecutorMessage::CloseConnection(_uid) => Ok(Disposition::Drain),
+        other => Err(ExecutorError::UnexpectedMessage(format!("{other:?}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconnect_delay_grows_then_caps() {
+        // Early attempts follow 2^(attempt-1) seconds; jitter only ever adds, so the whole-second
+        // floor is exactly the (uncapped) base.
+        assert_eq!(reconnect_delay(1, 60).as_secs(), 1);
+        assert_eq!(reconnect_delay(2, 60).as_secs(), 2);
+        assert_eq!(reconnect_delay(3, 60).as_secs(), 4);
+        // Once the base exceeds the cap the delay is pinned to it (plus sub-second jitter).
+        assert_eq!(reconnect_delay(100, 60).as_secs(), 60);
+    }
+
+    #[test]
+    fn reconnect_delay_jitter_stays_sub_second() {
+        // The jitter tops out just under a second, so the delay never reaches base + 1s.
+        for _ in 0..100 {
+            let d = reconnect_delay(3, 60);
+            assert!(d >= Duration::from_secs(4));
+            assert!(d < Duration::from_secs(5));
+        }
+    }
+
+    #[test]
+    fn reconnect_delay_handles_huge_attempt_without_overflow() {
+        // A shift past the width of u64 saturates rather than panicking, still capped.
+        assert_eq!(reconnect_delay(u32::MAX, 60).as_secs(), 60);
+    }
+}
+
+/// Map a message-decode failure onto the error taxonomy. An IO failure reading a frame's first
+/// byte is a [`ExecutorError::Disconnected`] for a peer hang-up, a [`ExecutorError::ReadTimeout`]
+/// for a timed-out read, and [`ExecutorError::Io`] otherwise; any other decode failure is
+/// unparseable protocol.
+fn classify_read(e: Error) -> ExecutorError {
+    match e {
+        Error::InvalidMarkerRead(io) => match io.kind() {
+            ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::UnexpectedEof => ExecutorError::Disconnected,
+            // A read timeout (only armed while draining) means "no message yet", not a failure.
+            ErrorKind::WouldBlock | ErrorKind::TimedOut => ExecutorError::ReadTimeout,
+            _ => ExecutorError::Io(io.to_string()),
+        },
+        other => ExecutorError::UnparseableProtocol(other.to_string()),
     }
-    Ok(())
 }
@@ -0,0 +1,224 @@
+//! Pushes build status outward to external services as jobs start and finish.
+//!
+//! Configured targets live under the `notifiers` section of [`Config`](crate::Config) and are
+//! fanned out from a background sender thread: [`Notifier::notify`] only enqueues, so a slow or
+//! flaky endpoint never stalls the build loop. Transient HTTP failures are retried a few times
+//! with a short delay before being logged and dropped.
+
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tracing::{error, warn};
+
+/// Number of delivery attempts per notification before giving up.
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+/// Delay between delivery attempts.
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// A configured notification target.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotifierConfig {
+    /// Post commit status to GitHub's commit-status API.
+    Github {
+        /// Personal access token / app token with `repo:status` scope.
+        token: String,
+        /// Optional API base override for GitHub Enterprise.
+        #[serde(default = "NotifierConfig::default_github_api")]
+        api_base: String,
+    },
+    /// POST the notification as JSON to an arbitrary URL.
+    Webhook { url: String },
+}
+
+impl NotifierConfig {
+    fn default_github_api() -> String {
+        "https://api.github.com".to_string()
+    }
+}
+
+/// Outcome of a build job, mapped onto each target's notion of commit status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildState {
+    Pending,
+    Success,
+    Failure,
+}
+
+impl BuildState {
+    /// GitHub commit-status `state` string.
+    fn github_state(self) -> &'static str {
+        match self {
+            BuildState::Pending => "pending",
+            BuildState::Success => "success",
+            BuildState::Failure => "failure",
+        }
+    }
+}
+
+/// A single status update to deliver to every configured target.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub repo_url: String,
+    pub reference: String,
+    pub job_name: String,
+    pub state: BuildState,
+}
+
+/// Handle to the background sender thread. Cloning the handle is cheap and shares the queue.
+#[derive(Clone)]
+pub struct Notifier {
+    tx: Option<Sender<Notification>>,
+}
+
+impl Notifier {
+    /// Spawn the background sender for the configured targets. With no targets, `notify` becomes a
+    /// no-op so callers need not special-case an unconfigured executor.
+    pub fn new(targets: Vec<NotifierConfig>) -> Self {
+        if targets.is_empty() {
+            return Self { tx: None };
+        }
+        let (tx, rx) = mpsc::channel::<Notification>();
+        thread::spawn(move || {
+            let client = reqwest::blocking::Client::new();
+            while let Ok(notification) = rx.recv() {
+                for target in &targets {
+                    deliver(&client, target, &notification);
+                }
+            }
+        });
+        Self { tx: Some(tx) }
+    }
+
+    /// Enqueue a status update. Never blocks on the network.
+    pub fn notify(&self, notification: Notification) {
+        if let Some(tx) = &self.tx {
+            if let Err(e) = tx.send(notification) {
+                warn!("Dropping notification, sender thread is gone: {e}");
+            }
+        }
+    }
+}
+
+/// Deliver a single notification to a single target, retrying transient failures.
+fn deliver(client: &reqwest::blocking::Client, target: &NotifierConfig, n: &Notification) {
+    for attempt in 1..=MAX_DELIVERY_ATTEMPTS {
+        let outcome = match target {
+            NotifierConfig::Github { token, api_base } => deliver_github(client, token, api_base, n),
+            NotifierConfig::Webhook { url } => deliver_webhook(client, url, n),
+        };
+        match outcome {
+            Ok(()) => return,
+            Err(e) => {
+                warn!("Notification delivery attempt {attempt} failed: {e}");
+                if attempt < MAX_DELIVERY_ATTEMPTS {
+                    thread::sleep(RETRY_DELAY);
+                }
+            }
+        }
+    }
+    error!(
+        "Giving up delivering {:?} notification for {}",
+        n.state, n.job_name
+    );
+}
+
+fn deliver_github(
+    client: &reqwest::blocking::Client,
+    token: &str,
+    api_base: &str,
+    n: &Notification,
+) -> reqwest::Result<()> {
+    let Some((owner, repo)) = parse_owner_repo(&n.repo_url) else {
+        warn!("Could not derive owner/repo from {}", n.repo_url);
+        return Ok(());
+    };
+    let url = format!("{api_base}/repos/{owner}/{repo}/statuses/{}", n.reference);
+    let body = serde_json::json!({
+        "state": n.state.github_state(),
+        "context": format!("waterci/{}", n.job_name),
+        "description": format!("waterci job {}", n.job_name),
+    });
+    client
+        .post(url)
+        .header("User-Agent", "waterci-executor")
+        .header("Authorization", format!("token {token}"))
+        .json(&body)
+        .send()?
+        .error_for_status()?;
+    Ok(())
+}
+
+fn deliver_webhook(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    n: &Notification,
+) -> reqwest::Result<()> {
+    let body = serde_json::json!({
+        "repo_url": n.repo_url,
+        "reference": n.reference,
+        "job_name": n.job_name,
+        "state": n.state.github_state(),
+    });
+    client.post(url).json(&body).send()?.error_for_status()?;
+    Ok(())
+}
+
+/// Best-effort extraction of `owner/repo` from a GitHub clone URL (https or ssh form).
+fn parse_owner_repo(repo_url: &str) -> Option<(String, String)> {
+    // Only GitHub URLs carry an owner/repo we can post commit status to; `rsplit_once` yields
+    // `None` for anything else so non-GitHub remotes are skipped rather than mis-parsed.
+    let path = repo_url
+        .trim_end_matches(".git")
+        .rsplit_once("github.com")?
+        .1
+        .trim_start_matches([':', '/']);
+    let mut parts = path.splitn(2, '/');
+    let owner = parts.next()?.to_string();
+    let repo = parts.next()?.to_string();
+    if owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+    Some((owner, repo))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_https_clone_url() {
+        assert_eq!(
+            parse_owner_repo("https://github.com/WaterCI/simple-executor.git"),
+            Some(("WaterCI".to_string(), "simple-executor".to_string()))
+        );
+    }
+
+    #[test]
+    fn parses_ssh_clone_url() {
+        assert_eq!(
+            parse_owner_repo("git@github.com:WaterCI/simple-executor.git"),
+            Some(("WaterCI".to_string(), "simple-executor".to_string()))
+        );
+    }
+
+    #[test]
+    fn parses_https_url_without_git_suffix() {
+        assert_eq!(
+            parse_owner_repo("https://github.com/WaterCI/simple-executor"),
+            Some(("WaterCI".to_string(), "simple-executor".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_non_github_url() {
+        assert_eq!(parse_owner_repo("https://gitlab.com/owner/repo.git"), None);
+    }
+
+    #[test]
+    fn rejects_url_missing_repo() {
+        assert_eq!(parse_owner_repo("https://github.com/owner"), None);
+    }
+}